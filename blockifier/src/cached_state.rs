@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
-use starknet_api::state::StorageKey;
+use starknet_api::state::{ContractClass, StorageKey};
 
 #[cfg(test)]
 #[path = "cached_state_test.rs"]
@@ -18,11 +18,17 @@ pub struct CachedState<SR: StateReader> {
     pub state_reader: SR,
     // Invariant: the cache should remain private.
     cache: StateCache,
+    // Compiled/converted contract classes, keyed by class hash, so each class is resolved once.
+    class_hash_to_class: HashMap<ClassHash, ContractClass>,
 }
 
 impl<SR: StateReader> CachedState<SR> {
     pub fn new(state_reader: SR) -> Self {
-        Self { state_reader, cache: StateCache::default() }
+        Self {
+            state_reader,
+            cache: StateCache::default(),
+            class_hash_to_class: HashMap::default(),
+        }
     }
 
     pub fn get_storage_at(
@@ -48,6 +54,114 @@ impl<SR: StateReader> CachedState<SR> {
     ) {
         self.cache.set_storage_writes(contract_address, key, value);
     }
+
+    pub fn get_nonce_at(&mut self, contract_address: ContractAddress) -> Result<&Nonce> {
+        if self.cache.get_nonce_at(contract_address).is_none() {
+            let nonce = self.state_reader.get_nonce_at(contract_address)?;
+            self.cache.set_nonce_initial_value(contract_address, nonce);
+        }
+
+        self.cache.get_nonce_at(contract_address).with_context(|| {
+            format!("Cannot retrieve the nonce of '{contract_address:?}' from the cache.")
+        })
+    }
+
+    pub fn increment_nonce(&mut self, contract_address: ContractAddress) -> Result<()> {
+        let current_nonce = *self.get_nonce_at(contract_address)?;
+        let current_nonce_as_u64 = felt_to_u64(&current_nonce.0)?;
+        let next_nonce_value = current_nonce_as_u64
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("Nonce overflow at address '{contract_address:?}'."))?;
+        self.cache.set_nonce_write(contract_address, Nonce(StarkFelt::from(next_nonce_value)));
+        Ok(())
+    }
+
+    pub fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> Result<&ClassHash> {
+        if self.cache.get_class_hash_at(contract_address).is_none() {
+            let class_hash = self.state_reader.get_class_hash_at(contract_address)?;
+            self.cache.set_class_hash_initial_value(contract_address, class_hash);
+        }
+
+        self.cache.get_class_hash_at(contract_address).with_context(|| {
+            format!("Cannot retrieve the class hash of '{contract_address:?}' from the cache.")
+        })
+    }
+
+    fn set_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> Result<()> {
+        let current_class_hash = *self.get_class_hash_at(contract_address)?;
+        if current_class_hash != ClassHash::default() {
+            bail!("Cannot deploy to '{contract_address:?}', already assigned a class hash.");
+        }
+        self.cache.set_class_hash_write(contract_address, class_hash);
+        Ok(())
+    }
+
+    /// Assigns a class hash to an unassigned address, registering a newly deployed contract.
+    pub fn deploy_contract(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> Result<()> {
+        self.set_class_hash_at(contract_address, class_hash)
+    }
+
+    /// Returns the contract class of the given class hash, fetching it from state on first use and
+    /// reusing the cached copy on subsequent calls.
+    pub fn get_contract_class(&mut self, class_hash: &ClassHash) -> Result<&ContractClass> {
+        if !self.class_hash_to_class.contains_key(class_hash) {
+            let contract_class = self.state_reader.get_contract_class(class_hash)?;
+            self.class_hash_to_class.insert(*class_hash, contract_class);
+        }
+
+        self.class_hash_to_class.get(class_hash).with_context(|| {
+            format!("Cannot retrieve the contract class of '{class_hash:?}' from the cache.")
+        })
+    }
+
+    /// Returns the net effect of all writes performed on this state as a `CommitmentStateDiff`.
+    ///
+    /// Only storage cells whose value differs from the on-chain value are emitted, so no-op writes
+    /// do not bloat the diff. Cells written without a prior read carry no cached initial value, so
+    /// their current value is fetched from the underlying reader to perform the comparison.
+    pub fn to_state_diff(&self) -> Result<CommitmentStateDiff> {
+        let mut storage_updates: HashMap<ContractAddress, HashMap<StorageKey, StarkFelt>> =
+            HashMap::new();
+        for (&(contract_address, key), &value) in self.cache.storage_writes.iter() {
+            let initial_value = match self.cache.storage_initial_values.get(&(contract_address, key))
+            {
+                Some(initial_value) => *initial_value,
+                None => self.state_reader.get_storage_at(contract_address, key)?,
+            };
+            if initial_value == value {
+                continue;
+            }
+            storage_updates.entry(contract_address).or_default().insert(key, value);
+        }
+
+        Ok(CommitmentStateDiff {
+            storage_updates,
+            nonce_updates: self.cache.nonce_writes.clone(),
+            class_hash_updates: self.cache.class_hash_writes.clone(),
+        })
+    }
+}
+
+/// The net set of mutations a transaction applies to the state, grouped by kind.
+///
+/// This is the minimal information downstream sequencer/state-commitment code needs in order to
+/// advance the global state: the storage cells that actually changed, the nonces that were bumped,
+/// and the class hashes assigned to newly deployed contracts.
+// TODO(Noa, 31/01/2023): Add declared classes once a declare path tracks them separately from the
+// execution read cache.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CommitmentStateDiff {
+    pub storage_updates: HashMap<ContractAddress, HashMap<StorageKey, StarkFelt>>,
+    pub nonce_updates: HashMap<ContractAddress, Nonce>,
+    pub class_hash_updates: HashMap<ContractAddress, ClassHash>,
 }
 
 /// A read-only API for accessing StarkNet global state.
@@ -70,6 +184,35 @@ pub trait StateReader {
     fn get_class_hash_at(&self, _contract_address: ContractAddress) -> Result<ClassHash> {
         unimplemented!();
     }
+
+    /// Returns the contract class of the given class hash.
+    fn get_contract_class(&self, _class_hash: &ClassHash) -> Result<ContractClass> {
+        unimplemented!();
+    }
+}
+
+/// Allows a `StateReader` to be shared, read-only, across threads so that each worker can layer its
+/// own read-through cache on top of a single backing reader.
+impl<SR: StateReader> StateReader for std::sync::Arc<SR> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> Result<StarkFelt> {
+        (**self).get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> Result<Nonce> {
+        (**self).get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> Result<ClassHash> {
+        (**self).get_class_hash_at(contract_address)
+    }
+
+    fn get_contract_class(&self, class_hash: &ClassHash) -> Result<ContractClass> {
+        (**self).get_contract_class(class_hash)
+    }
 }
 
 type ContractStorageKey = (ContractAddress, StorageKey);
@@ -78,6 +221,9 @@ type ContractStorageKey = (ContractAddress, StorageKey);
 #[derive(Default)]
 pub struct DictStateReader {
     pub contract_storage_key_to_value: HashMap<ContractStorageKey, StarkFelt>,
+    pub address_to_nonce: HashMap<ContractAddress, Nonce>,
+    pub address_to_class_hash: HashMap<ContractAddress, ClassHash>,
+    pub class_hash_to_contract_class: HashMap<ClassHash, ContractClass>,
 }
 
 impl StateReader for DictStateReader {
@@ -94,6 +240,24 @@ impl StateReader for DictStateReader {
             .unwrap_or_else(default_storage_value);
         Ok(value)
     }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> Result<Nonce> {
+        let nonce = self.address_to_nonce.get(&contract_address).copied().unwrap_or_default();
+        Ok(nonce)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> Result<ClassHash> {
+        let class_hash =
+            self.address_to_class_hash.get(&contract_address).copied().unwrap_or_default();
+        Ok(class_hash)
+    }
+
+    fn get_contract_class(&self, class_hash: &ClassHash) -> Result<ContractClass> {
+        self.class_hash_to_contract_class
+            .get(class_hash)
+            .cloned()
+            .ok_or_else(|| anyhow!("Class hash '{class_hash:?}' is not declared."))
+    }
 }
 
 /// Caches read and write requests.
@@ -101,13 +265,13 @@ impl StateReader for DictStateReader {
 #[derive(Default)]
 struct StateCache {
     // Reader's cached information; initial values, read before any write operation (per cell).
-    _nonce_initial_values: HashMap<ContractAddress, Nonce>,
-    _class_hash_initial_values: HashMap<ContractAddress, ClassHash>,
+    nonce_initial_values: HashMap<ContractAddress, Nonce>,
+    class_hash_initial_values: HashMap<ContractAddress, ClassHash>,
     storage_initial_values: HashMap<ContractStorageKey, StarkFelt>,
 
     // Writer's cached information.
-    _nonce_writes: HashMap<ContractAddress, Nonce>,
-    _class_hash_writes: HashMap<ContractAddress, ClassHash>,
+    nonce_writes: HashMap<ContractAddress, Nonce>,
+    class_hash_writes: HashMap<ContractAddress, ClassHash>,
     storage_writes: HashMap<ContractStorageKey, StarkFelt>,
 }
 
@@ -123,6 +287,38 @@ impl StateCache {
             .or_else(|| self.storage_initial_values.get(&contract_storage_key))
     }
 
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> Option<&Nonce> {
+        self.nonce_writes
+            .get(&contract_address)
+            .or_else(|| self.nonce_initial_values.get(&contract_address))
+    }
+
+    fn set_nonce_initial_value(&mut self, contract_address: ContractAddress, nonce: Nonce) {
+        self.nonce_initial_values.insert(contract_address, nonce);
+    }
+
+    fn set_nonce_write(&mut self, contract_address: ContractAddress, nonce: Nonce) {
+        self.nonce_writes.insert(contract_address, nonce);
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> Option<&ClassHash> {
+        self.class_hash_writes
+            .get(&contract_address)
+            .or_else(|| self.class_hash_initial_values.get(&contract_address))
+    }
+
+    fn set_class_hash_initial_value(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) {
+        self.class_hash_initial_values.insert(contract_address, class_hash);
+    }
+
+    fn set_class_hash_write(&mut self, contract_address: ContractAddress, class_hash: ClassHash) {
+        self.class_hash_writes.insert(contract_address, class_hash);
+    }
+
     pub fn set_storage_initial_values(
         &mut self,
         contract_address: ContractAddress,
@@ -144,6 +340,13 @@ impl StateCache {
     }
 }
 
+/// Interprets the low 8 bytes of a felt as a big-endian `u64`.
+// TODO(Noa, 30/12/22): Remove once nonces carry a native integer representation.
+fn felt_to_u64(felt: &StarkFelt) -> Result<u64> {
+    let as_bytes: [u8; 8] = felt.bytes()[24..32].try_into()?;
+    Ok(u64::from_be_bytes(as_bytes))
+}
+
 fn uninitialized_felt() -> StarkFelt {
     StarkFelt::default()
 }
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use super::{CachedState, DictStateReader};
+
+fn contract_address(value: u64) -> ContractAddress {
+    ContractAddress(PatriciaKey::try_from(StarkFelt::from(value)).unwrap())
+}
+
+fn storage_key(value: u64) -> StorageKey {
+    StorageKey(PatriciaKey::try_from(StarkFelt::from(value)).unwrap())
+}
+
+#[test]
+fn test_get_and_increment_nonce() {
+    let contract_address0 = contract_address(0x100);
+    let contract_address1 = contract_address(0x200);
+    let initial_nonce = Nonce(StarkFelt::from(1_u64));
+    let state_reader = DictStateReader {
+        address_to_nonce: HashMap::from([
+            (contract_address0, initial_nonce),
+            (contract_address1, initial_nonce),
+        ]),
+        ..Default::default()
+    };
+    let mut state = CachedState::new(state_reader);
+
+    assert_eq!(*state.get_nonce_at(contract_address0).unwrap(), Nonce(StarkFelt::from(1_u64)));
+    state.increment_nonce(contract_address0).unwrap();
+    assert_eq!(*state.get_nonce_at(contract_address0).unwrap(), Nonce(StarkFelt::from(2_u64)));
+    state.increment_nonce(contract_address0).unwrap();
+    assert_eq!(*state.get_nonce_at(contract_address0).unwrap(), Nonce(StarkFelt::from(3_u64)));
+
+    // Incrementing one address leaves the other untouched.
+    assert_eq!(*state.get_nonce_at(contract_address1).unwrap(), Nonce(StarkFelt::from(1_u64)));
+}
+
+#[test]
+fn test_deploy_contract_rejects_redeploy() {
+    let contract_address = contract_address(0x100);
+    let class_hash = ClassHash(StarkFelt::from(0x1234_u64));
+    let mut state = CachedState::new(DictStateReader::default());
+
+    state.deploy_contract(contract_address, class_hash).unwrap();
+    assert_eq!(*state.get_class_hash_at(contract_address).unwrap(), class_hash);
+
+    // A second deployment to an already-assigned address is rejected.
+    assert!(state.deploy_contract(contract_address, class_hash).is_err());
+}
+
+#[test]
+fn test_to_state_diff_omits_noop_writes() {
+    let contract_address = contract_address(0x100);
+    let key = storage_key(0x10);
+    let existing_value = StarkFelt::from(0x22_u64);
+    let state_reader = DictStateReader {
+        contract_storage_key_to_value: HashMap::from([((contract_address, key), existing_value)]),
+        ..Default::default()
+    };
+    let mut state = CachedState::new(state_reader);
+
+    // A write-without-read equal to the on-chain value is deduped away.
+    state.set_storage_at(contract_address, key, existing_value);
+    assert!(state.to_state_diff().unwrap().storage_updates.is_empty());
+
+    // A write that changes the value is emitted.
+    let new_value = StarkFelt::from(0x99_u64);
+    state.set_storage_at(contract_address, key, new_value);
+    assert_eq!(
+        state.to_state_diff().unwrap().storage_updates,
+        HashMap::from([(contract_address, HashMap::from([(key, new_value)]))])
+    );
+}
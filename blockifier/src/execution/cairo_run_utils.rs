@@ -12,12 +12,13 @@ use cairo_rs::types::errors::program_errors::ProgramError;
 use cairo_rs::types::program::Program;
 use cairo_rs::types::relocatable::MaybeRelocatable;
 use cairo_rs::vm::errors::vm_errors::VirtualMachineError;
-use cairo_rs::vm::runners::cairo_runner::CairoRunner;
+use cairo_rs::vm::runners::cairo_runner::{CairoRunner, ExecutionResources};
 use cairo_rs::vm::vm_core::VirtualMachine;
 use num_bigint::{BigInt, Sign};
 use num_traits::Signed;
 use starknet_api::StarkFelt;
 
+use crate::cached_state::{CachedState, StateReader};
 use crate::execution::entry_point::CallEntryPoint;
 
 #[derive(Debug)]
@@ -69,14 +70,25 @@ pub fn usize_try_from_starkfelt(felt: &StarkFelt) -> Result<usize> {
     Ok(usize::from_be_bytes(as_bytes))
 }
 
-/// Executes a specific call to a contract entry point and returns its output.
-pub fn execute_call_entry_point(
+/// The outcome of executing a call entry point: its return data and the resources it consumed.
+pub struct CallExecution {
+    pub return_data: Vec<StarkFelt>,
+    pub resources: ExecutionResources,
+}
+
+/// Executes a specific call to a contract entry point and returns its output and resource usage.
+///
+/// The contract class is resolved from `state` via the call's class hash and reused across calls,
+/// rather than being carried inside the call itself.
+pub fn execute_call_entry_point<SR: StateReader>(
     call_entry_point: &CallEntryPoint,
+    state: &mut CachedState<SR>,
     config: CairoRunConfig,
     hint_executor: &dyn HintProcessor,
-) -> Result<Vec<StarkFelt>> {
+) -> Result<CallExecution> {
     // Instantiate Cairo runner.
-    let program = convert_program_to_cairo_runner_format(&call_entry_point.contract_class.program)?;
+    let contract_class = state.get_contract_class(&call_entry_point.class_hash)?;
+    let program = convert_program_to_cairo_runner_format(&contract_class.program)?;
     let layout: String = config.layout.into();
     let mut cairo_runner = CairoRunner::new(&program, &layout, config.proof_mode)?;
     let mut vm = VirtualMachine::new(program.prime, config.enable_trace);
@@ -103,8 +115,8 @@ pub fn execute_call_entry_point(
             .collect::<Vec<MaybeRelocatable>>(),
     ));
 
-    // Resolve initial PC from EP indicator.
-    let entry_point = call_entry_point.find_entry_point_in_contract()?;
+    // Resolve initial PC from EP indicator, selecting the table matching the entry-point type.
+    let entry_point = call_entry_point.find_entry_point_in_contract(contract_class)?;
     let entry_point_pc = usize_try_from_starkfelt(&entry_point.offset.0)?;
 
     // Run.
@@ -118,7 +130,10 @@ pub fn execute_call_entry_point(
         hint_executor,
     )?;
 
-    extract_execution_return_data(&vm)
+    let return_data = extract_execution_return_data(&vm)?;
+    let resources = cairo_runner.get_execution_resources(&vm)?;
+
+    Ok(CallExecution { return_data, resources })
 }
 
 fn extract_execution_return_data(vm: &VirtualMachine) -> Result<Vec<StarkFelt>> {
@@ -147,6 +162,10 @@ fn extract_execution_return_data(vm: &VirtualMachine) -> Result<Vec<StarkFelt>>
     values
 }
 
+/// Converts a deprecated (Cairo 0.x/1.x) contract program into the runner's `Program` format.
+///
+/// Bare and `_builtin`-suffixed builtin names are normalized so either naming feeds the runner
+/// identically. The Cairo 2.x (Sierra) class schema is a distinct type and is not handled here.
 // TODO(Noa, 01/12/2022): Change this temporary solution.
 pub fn convert_program_to_cairo_runner_format(
     program: &starknet_api::Program,
@@ -164,7 +183,12 @@ pub fn convert_program_to_cairo_runner_format(
     };
 
     Ok(Program {
-        builtins: serde_json::from_value::<Vec<String>>(program.builtins)?,
+        // Deprecated classes list bare builtin names ("range_check") while newer classes suffix
+        // them with "_builtin"; normalize so both layouts feed the runner identically.
+        builtins: serde_json::from_value::<Vec<String>>(program.builtins)?
+            .into_iter()
+            .map(|builtin| builtin.trim_end_matches("_builtin").to_owned())
+            .collect(),
         prime: deserialize_bigint_hex(program.prime)?,
         data: deserialize_array_of_bigint_hex(program.data)?,
         constants: {
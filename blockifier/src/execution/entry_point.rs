@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Result};
+use starknet_api::core::{ClassHash, EntryPointSelector};
+use starknet_api::state::{ContractClass, EntryPoint, EntryPointType};
+use starknet_api::transaction::CallData;
+
+/// A call to a contract entry point.
+///
+/// The class is resolved from state via `class_hash`; the `entry_point_type`/`entry_point_selector`
+/// pair selects which entry point of that class to run.
+pub struct CallEntryPoint {
+    pub class_hash: ClassHash,
+    pub entry_point_type: EntryPointType,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: CallData,
+}
+
+impl CallEntryPoint {
+    /// Resolves this call's entry point within `contract_class`.
+    ///
+    /// The lookup is scoped to the table matching `entry_point_type` (External, L1Handler, or
+    /// Constructor), so a constructor and an external sharing a selector do not collide.
+    pub fn find_entry_point_in_contract(
+        &self,
+        contract_class: &ContractClass,
+    ) -> Result<EntryPoint> {
+        let entry_points_of_same_type = contract_class
+            .entry_points_by_type
+            .get(&self.entry_point_type)
+            .ok_or_else(|| {
+                anyhow!("No entry points of type '{:?}' in the contract class.", self.entry_point_type)
+            })?;
+
+        let filtered_entry_points: Vec<&EntryPoint> = entry_points_of_same_type
+            .iter()
+            .filter(|ep| ep.selector == self.entry_point_selector)
+            .collect();
+
+        match &filtered_entry_points[..] {
+            [] => Err(anyhow!(
+                "Entry point '{:?}' of type '{:?}' not found in the contract class.",
+                self.entry_point_selector,
+                self.entry_point_type
+            )),
+            [entry_point] => Ok((*entry_point).clone()),
+            _ => Err(anyhow!(
+                "Multiple entry points '{:?}' of type '{:?}' found in the contract class.",
+                self.entry_point_selector,
+                self.entry_point_type
+            )),
+        }
+    }
+}
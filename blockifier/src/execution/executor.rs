@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::cached_state::{CachedState, StateReader};
+use crate::execution::cairo_run_utils::CallExecution;
+use crate::execution::entry_point::CallEntryPoint;
+
+/// Tunables bounding how many entry-point calls run — and wait — at once.
+pub struct ExecutorConfig {
+    /// The maximum number of VM instances running concurrently.
+    pub max_in_flight: usize,
+    /// The maximum number of submitted-but-not-yet-running calls. Submissions beyond this limit
+    /// block until a slot frees.
+    pub max_queue_depth: usize,
+}
+
+impl ExecutorConfig {
+    pub fn new(max_in_flight: usize, max_queue_depth: usize) -> Result<Self> {
+        if max_in_flight == 0 {
+            bail!("`max_in_flight` must be positive.");
+        }
+        if max_queue_depth == 0 {
+            bail!("`max_queue_depth` must be positive.");
+        }
+        Ok(Self { max_in_flight, max_queue_depth })
+    }
+}
+
+/// A throttled executor that runs a batch of `CallEntryPoint`s concurrently over a shared state.
+///
+/// Each worker layers its own read-through cache (a [`CachedState`]) on top of the shared
+/// [`StateReader`], so reads are cached per worker while the backing reader is shared. At most
+/// `max_in_flight` VM instances run at once, and no more than `max_queue_depth` calls sit pending.
+pub struct BoundedExecutor {
+    config: ExecutorConfig,
+}
+
+impl BoundedExecutor {
+    pub fn new(config: ExecutorConfig) -> Self {
+        Self { config }
+    }
+
+    /// Executes `calls` concurrently and returns their results in submission order.
+    ///
+    /// `run` is invoked once per call with a worker-local cached state; its success or error is
+    /// reported per call, so a single failing call does not abort the batch.
+    ///
+    /// Each worker reuses a single [`CachedState`] across every call it pops, so `run` must be
+    /// write-independent: any writes it performs (storage/nonce/class-hash) persist into subsequent,
+    /// unrelated calls handled by the same worker. The read-only `execute_call_entry_point` path
+    /// satisfies this.
+    pub fn execute<SR, F>(
+        &self,
+        state_reader: Arc<SR>,
+        calls: Vec<CallEntryPoint>,
+        run: F,
+    ) -> Vec<Result<CallExecution>>
+    where
+        SR: StateReader + Send + Sync + 'static,
+        F: Fn(&mut CachedState<Arc<SR>>, &CallEntryPoint) -> Result<CallExecution>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let n_calls = calls.len();
+        let run = Arc::new(run);
+        // Bounded queue of (submission index, call). `None` once draining is done.
+        let queue = Arc::new((Mutex::new(Queue::with_capacity(self.config.max_queue_depth)), Condvar::new()));
+        // Results slotted by submission index so the output order matches the input order.
+        let results: Arc<Vec<Mutex<Option<Result<CallExecution>>>>> =
+            Arc::new((0..n_calls).map(|_| Mutex::new(None)).collect());
+
+        let workers: Vec<_> = (0..self.config.max_in_flight.min(n_calls.max(1)))
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let results = Arc::clone(&results);
+                let state_reader = Arc::clone(&state_reader);
+                let run = Arc::clone(&run);
+                thread::spawn(move || {
+                    let mut state = CachedState::new(Arc::clone(&state_reader));
+                    while let Some((index, call)) = pop(&queue) {
+                        // A panic in `run` is caught and recorded as a per-call error rather than
+                        // unwinding the worker, so one bad call cannot abort the whole batch. The
+                        // possibly-inconsistent cache is discarded before the next call.
+                        let outcome =
+                            match std::panic::catch_unwind(AssertUnwindSafe(|| run(&mut state, &call))) {
+                                Ok(outcome) => outcome,
+                                Err(_) => {
+                                    state = CachedState::new(Arc::clone(&state_reader));
+                                    Err(anyhow!("Call at submission index {index} panicked."))
+                                }
+                            };
+                        *results[index].lock().expect("results mutex poisoned") = Some(outcome);
+                    }
+                })
+            })
+            .collect();
+
+        for (index, call) in calls.into_iter().enumerate() {
+            push(&queue, (index, call));
+        }
+        close(&queue);
+
+        for worker in workers {
+            worker.join().expect("executor worker panicked");
+        }
+
+        Arc::try_unwrap(results)
+            .unwrap_or_else(|_| panic!("results still shared after join"))
+            .into_iter()
+            .map(|slot| slot.into_inner().expect("results mutex poisoned").expect("slot unfilled"))
+            .collect()
+    }
+}
+
+struct Queue {
+    capacity: usize,
+    items: VecDeque<(usize, CallEntryPoint)>,
+    closed: bool,
+}
+
+impl Queue {
+    fn with_capacity(capacity: usize) -> Self {
+        Self { capacity, items: VecDeque::new(), closed: false }
+    }
+}
+
+type SharedQueue = Arc<(Mutex<Queue>, Condvar)>;
+
+fn push(queue: &SharedQueue, item: (usize, CallEntryPoint)) {
+    let (lock, cvar) = &**queue;
+    let mut queue = lock.lock().expect("queue mutex poisoned");
+    while queue.items.len() >= queue.capacity {
+        queue = cvar.wait(queue).expect("queue mutex poisoned");
+    }
+    queue.items.push_back(item);
+    cvar.notify_all();
+}
+
+fn pop(queue: &SharedQueue) -> Option<(usize, CallEntryPoint)> {
+    let (lock, cvar) = &**queue;
+    let mut queue = lock.lock().expect("queue mutex poisoned");
+    loop {
+        if let Some(item) = queue.items.pop_front() {
+            cvar.notify_all();
+            return Some(item);
+        }
+        if queue.closed {
+            return None;
+        }
+        queue = cvar.wait(queue).expect("queue mutex poisoned");
+    }
+}
+
+fn close(queue: &SharedQueue) {
+    let (lock, cvar) = &**queue;
+    lock.lock().expect("queue mutex poisoned").closed = true;
+    cvar.notify_all();
+}
@@ -0,0 +1,89 @@
+use std::num::NonZeroU128;
+
+use anyhow::{anyhow, Result};
+use cairo_rs::vm::runners::cairo_runner::ExecutionResources;
+
+/// The weight, in gas, of a single Cairo step.
+pub const STEP_GAS_WEIGHT: u64 = 1;
+
+/// Per-instance gas weights for the builtins the `all` layout can instantiate.
+///
+/// These mirror the Cairo cost model: each builtin invocation is priced as a fixed multiple of a
+/// step. They are compile-time constants that `calculate_fee` always uses; it takes no weight
+/// table from its caller. Builtins absent from this table
+/// are priced at zero, so layouts that instantiate extra (possibly zero-count) builtins never make
+/// fee computation fail.
+pub const OUTPUT_GAS_WEIGHT: u64 = 0;
+pub const RANGE_CHECK_GAS_WEIGHT: u64 = 16;
+pub const PEDERSEN_GAS_WEIGHT: u64 = 32;
+pub const BITWISE_GAS_WEIGHT: u64 = 64;
+pub const POSEIDON_GAS_WEIGHT: u64 = 256;
+pub const EC_OP_GAS_WEIGHT: u64 = 1024;
+pub const ECDSA_GAS_WEIGHT: u64 = 2048;
+pub const KECCAK_GAS_WEIGHT: u64 = 2048;
+pub const SEGMENT_ARENA_GAS_WEIGHT: u64 = 0;
+
+/// Returns the per-instance gas weight of the builtin identified by `name`.
+///
+/// Unknown builtins are priced at zero rather than rejected, so an execution run under a layout
+/// that instantiates builtins the fee schedule does not enumerate still prices successfully.
+fn builtin_gas_weight(name: &str) -> u64 {
+    // Builtin names carry a "_builtin" suffix in the runner's counters.
+    match name.trim_end_matches("_builtin") {
+        "output" => OUTPUT_GAS_WEIGHT,
+        "range_check" => RANGE_CHECK_GAS_WEIGHT,
+        "pedersen" => PEDERSEN_GAS_WEIGHT,
+        "bitwise" => BITWISE_GAS_WEIGHT,
+        "poseidon" => POSEIDON_GAS_WEIGHT,
+        "ec_op" => EC_OP_GAS_WEIGHT,
+        "ecdsa" => ECDSA_GAS_WEIGHT,
+        "keccak" => KECCAK_GAS_WEIGHT,
+        "segment_arena" => SEGMENT_ARENA_GAS_WEIGHT,
+        _ => 0,
+    }
+}
+
+/// An amount of gas consumed by an execution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GasAmount(pub u64);
+
+impl GasAmount {
+    pub fn checked_add(self, rhs: GasAmount) -> Option<GasAmount> {
+        self.0.checked_add(rhs.0).map(GasAmount)
+    }
+
+    pub fn saturating_add(self, rhs: GasAmount) -> GasAmount {
+        GasAmount(self.0.saturating_add(rhs.0))
+    }
+
+    /// Prices this amount of gas, returning `None` on overflow.
+    pub fn checked_mul(self, gas_price: GasPrice) -> Option<Fee> {
+        (self.0 as u128).checked_mul(gas_price.0.get()).map(Fee)
+    }
+}
+
+/// The price of a single unit of gas. Guaranteed to be non-zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasPrice(pub NonZeroU128);
+
+/// A fee, denominated in the fee token's smallest unit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fee(pub u128);
+
+/// Converts execution resources to a gas amount and prices it at `gas_price`.
+///
+/// The gas cost is the maximum of the step cost (`n_steps * STEP_GAS_WEIGHT`) and each builtin's
+/// weighted cost (`count * weight`), rather than their sum.
+pub fn calculate_fee(resources: &ExecutionResources, gas_price: GasPrice) -> Result<Fee> {
+    let step_gas = (resources.n_steps as u64).saturating_mul(STEP_GAS_WEIGHT);
+
+    let mut gas_amount = GasAmount(step_gas);
+    for (builtin_name, &count) in resources.builtin_instance_counter.iter() {
+        let builtin_gas = (count as u64).saturating_mul(builtin_gas_weight(builtin_name));
+        gas_amount = gas_amount.max(GasAmount(builtin_gas));
+    }
+
+    gas_amount
+        .checked_mul(gas_price)
+        .ok_or_else(|| anyhow!("Fee computation overflowed for {gas_amount:?} at {gas_price:?}."))
+}